@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
 use async_nats::{
@@ -10,9 +11,181 @@ use async_nats::{
     },
     Client, ConnectOptions,
 };
+use async_trait::async_trait;
+use base64::Engine;
+use bytes::Bytes;
+use object_store::{path::Path as ObjectPath, ObjectStore};
 
 use wadm::DEFAULT_EXPIRY_TIME;
 
+/// A minimal key/value abstraction over the durable backends wadm can persist manifests and
+/// snapshots to. This lets large manifests that exceed JetStream KV's per-value limits live in an
+/// external object store while smaller state keeps using KV, selected by config.
+#[async_trait]
+pub trait StateStore: Send + Sync {
+    /// Fetches the value stored under `key`, or `None` if it does not exist.
+    async fn get(&self, key: &str) -> Result<Option<Bytes>>;
+    /// Stores `value` under `key`, overwriting any previous value.
+    async fn put(&self, key: &str, value: Bytes) -> Result<()>;
+    /// Lists all keys currently present, optionally restricted to those starting with `prefix`.
+    ///
+    /// `prefix` is matched as a plain character-wise string prefix over the full key (the same
+    /// `str::starts_with` semantics for every backend), not as a path-segment prefix — so
+    /// `list(Some("app"))` returns both `app` and `apples`. Passing `None` returns every key.
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>>;
+    /// Removes the value stored under `key`. Removing a missing key is not an error.
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// A [`StateStore`] backed by a JetStream KV bucket, the default wadm persistence.
+pub struct JetStreamKvStore {
+    store: Store,
+}
+
+impl JetStreamKvStore {
+    pub fn new(store: Store) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl StateStore for JetStreamKvStore {
+    async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+        self.store.get(key).await.map_err(|e| anyhow!("{e:?}"))
+    }
+
+    async fn put(&self, key: &str, value: Bytes) -> Result<()> {
+        self.store
+            .put(key, value)
+            .await
+            .map(|_| ())
+            .map_err(|e| anyhow!("{e:?}"))
+    }
+
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+        let keys = self.store.keys().await.map_err(|e| anyhow!("{e:?}"))?;
+        let mut out = Vec::new();
+        for key in keys {
+            let key = key.map_err(|e| anyhow!("{e:?}"))?;
+            if prefix.map(|p| key.starts_with(p)).unwrap_or(true) {
+                out.push(key);
+            }
+        }
+        Ok(out)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.store.delete(key).await.map_err(|e| anyhow!("{e:?}"))
+    }
+}
+
+/// A [`StateStore`] backed by any `object_store`-supported backend (S3/GCS/Azure/local filesystem),
+/// constructed from a URL plus backend-specific credentials. Use this for large manifests and
+/// periodic snapshots that outgrow KV's per-value size limits.
+pub struct ObjectStoreBackend {
+    store: Arc<dyn ObjectStore>,
+    prefix: ObjectPath,
+}
+
+impl ObjectStoreBackend {
+    /// Builds a backend from a storage URL (e.g. `s3://bucket/prefix`, `file:///var/lib/wadm`) and
+    /// the credential key/value pairs that `object_store` expects for that scheme.
+    pub fn new(url: &str, options: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let url = url::Url::parse(url).map_err(|e| anyhow!("invalid object store url: {e}"))?;
+        let (store, prefix) = object_store::parse_url_opts(&url, options)
+            .map_err(|e| anyhow!("failed to build object store backend: {e}"))?;
+        Ok(Self {
+            store: Arc::from(store),
+            prefix,
+        })
+    }
+
+    fn location(&self, key: &str) -> ObjectPath {
+        self.prefix.child(key)
+    }
+}
+
+#[async_trait]
+impl StateStore for ObjectStoreBackend {
+    async fn get(&self, key: &str) -> Result<Option<Bytes>> {
+        match self.store.get(&self.location(key)).await {
+            Ok(result) => Ok(Some(result.bytes().await.map_err(|e| anyhow!("{e}"))?)),
+            Err(object_store::Error::NotFound { .. }) => Ok(None),
+            Err(e) => Err(anyhow!("{e}")),
+        }
+    }
+
+    async fn put(&self, key: &str, value: Bytes) -> Result<()> {
+        self.store
+            .put(&self.location(key), value.into())
+            .await
+            .map(|_| ())
+            .map_err(|e| anyhow!("{e}"))
+    }
+
+    async fn list(&self, prefix: Option<&str>) -> Result<Vec<String>> {
+        use futures::TryStreamExt;
+
+        // List everything under the backend prefix, reconstruct each key, then filter with the
+        // same character-wise `starts_with` the trait promises — `object_store`'s own prefix list
+        // matches whole path segments, which would give different results from the KV backend.
+        let objects: Vec<_> = self
+            .store
+            .list(Some(&self.prefix))
+            .map_err(|e| anyhow!("{e}"))
+            .try_collect()
+            .await?;
+        objects
+            .into_iter()
+            .map(|meta| {
+                meta.location
+                    .prefix_match(&self.prefix)
+                    .map(|parts| parts.collect::<Vec<_>>().join("/"))
+                    .ok_or_else(|| anyhow!("object {} is outside the configured prefix", meta.location))
+            })
+            .filter(|key| match (key, prefix) {
+                (Ok(key), Some(p)) => key.starts_with(p),
+                _ => true,
+            })
+            .collect()
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match self.store.delete(&self.location(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(anyhow!("{e}")),
+        }
+    }
+}
+
+/// Selects which [`StateStore`] backend wadm uses for manifest and snapshot persistence,
+/// configured alongside the NATS connection options.
+pub enum StateStoreConfig {
+    /// Persist state in a JetStream KV bucket (the default).
+    JetStreamKv { bucket: String, history: i64 },
+    /// Persist state in an external object store, keyed by URL and backend credentials.
+    ObjectStore {
+        url: String,
+        options: Vec<(String, String)>,
+    },
+}
+
+/// Builds the configured [`StateStore`], provisioning the JetStream KV bucket if needed.
+pub async fn build_state_store(
+    context: &Context,
+    config: StateStoreConfig,
+) -> Result<Box<dyn StateStore>> {
+    match config {
+        StateStoreConfig::JetStreamKv { bucket, history } => {
+            let store = ensure_kv_bucket(context, bucket, history).await?;
+            Ok(Box::new(JetStreamKvStore::new(store)))
+        }
+        StateStoreConfig::ObjectStore { url, options } => {
+            Ok(Box::new(ObjectStoreBackend::new(&url, options)?))
+        }
+    }
+}
+
 /// Creates a NATS client from the given options
 pub async fn get_client_and_context(
     url: String,
@@ -21,10 +194,11 @@ pub async fn get_client_and_context(
     jwt: Option<String>,
     creds_path: Option<PathBuf>,
 ) -> Result<(Client, Context)> {
-    let client = if seed.is_none() && jwt.is_none() && creds_path.is_none() {
+    let auth = AuthConfig::from_flags(seed, jwt, creds_path)?;
+    let client = if matches!(auth, AuthConfig::None) {
         async_nats::connect(url).await?
     } else {
-        let opts = build_nats_options(seed, jwt, creds_path).await?;
+        let opts = build_nats_options(auth).await?;
         async_nats::connect_with_options(url, opts).await?
     };
 
@@ -37,33 +211,122 @@ pub async fn get_client_and_context(
     Ok((client, context))
 }
 
-async fn build_nats_options(
-    seed: Option<String>,
-    jwt: Option<String>,
-    creds_path: Option<PathBuf>,
-) -> Result<ConnectOptions> {
-    match (seed, jwt, creds_path) {
-        (Some(seed), Some(jwt), None) => {
-            let jwt = resolve_jwt(jwt).await?;
-            let kp = std::sync::Arc::new(get_seed(seed).await?);
-
-            Ok(async_nats::ConnectOptions::with_jwt(jwt, move |nonce| {
-                let key_pair = kp.clone();
-                async move { key_pair.sign(&nonce).map_err(async_nats::AuthError::new) }
-            }))
-        }
-        (None, None, Some(creds)) => async_nats::ConnectOptions::with_credentials_file(creds)
+/// The authentication modes wadm can present to NATS. Mirrors the credential options async-nats
+/// supports so operators are not limited to seed+jwt or a creds file.
+pub enum AuthConfig {
+    /// No explicit credentials; connect anonymously.
+    None,
+    /// An operator-signed JWT paired with an nkey seed. Both may be inline values or file paths;
+    /// file paths are re-read on every sign so rotated credentials are picked up on reconnect.
+    Jwt { jwt: String, seed: String },
+    /// A NATS credentials (`.creds`) file bundling the JWT and seed.
+    Creds { path: PathBuf },
+    /// A bearer token.
+    Token { token: String },
+    /// A username and password pair.
+    UserPassword { user: String, password: String },
+    /// A TLS client certificate and its private key, for mutual-TLS authentication.
+    TlsClientCert { cert: PathBuf, key: PathBuf },
+}
+
+impl AuthConfig {
+    /// Derives an [`AuthConfig`] from the legacy seed/jwt/creds startup flags.
+    fn from_flags(
+        seed: Option<String>,
+        jwt: Option<String>,
+        creds_path: Option<PathBuf>,
+    ) -> Result<Self> {
+        match (seed, jwt, creds_path) {
+            (None, None, None) => Ok(AuthConfig::None),
+            (Some(seed), Some(jwt), None) => Ok(AuthConfig::Jwt { jwt, seed }),
+            (None, None, Some(path)) => Ok(AuthConfig::Creds { path }),
+            _ => Err(anyhow::anyhow!(
+                "Got too many options. Make sure to provide a seed and jwt or a creds path"
+            )),
+        }
+    }
+}
+
+pub async fn build_nats_options(auth: AuthConfig) -> Result<ConnectOptions> {
+    match auth {
+        AuthConfig::None => Ok(async_nats::ConnectOptions::new()),
+        AuthConfig::Jwt { jwt, seed } => {
+            // Re-read both the JWT and the seed on every (re)connect via the auth callback rather
+            // than capturing pre-resolved values once at startup, so a rotated creds/JWT/seed file
+            // is presented on the next reconnect instead of the stale credential.
+            let jwt = std::sync::Arc::new(jwt);
+            let seed = std::sync::Arc::new(seed);
+
+            Ok(async_nats::ConnectOptions::with_auth_callback(
+                move |nonce| {
+                    let jwt = jwt.clone();
+                    let seed = seed.clone();
+                    async move {
+                        let jwt = resolve_jwt((*jwt).clone())
+                            .await
+                            .map_err(async_nats::AuthError::new)?;
+                        let key_pair = get_seed((*seed).clone())
+                            .await
+                            .map_err(async_nats::AuthError::new)?;
+                        let signature = key_pair.sign(&nonce).map_err(async_nats::AuthError::new)?;
+                        let mut auth = async_nats::Auth::new();
+                        auth.jwt = Some(jwt);
+                        auth.signature = Some(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(signature));
+                        Ok(auth)
+                    }
+                },
+            ))
+        }
+        AuthConfig::Creds { path } => async_nats::ConnectOptions::with_credentials_file(path)
             .await
             .map_err(anyhow::Error::from),
-        _ => {
-            // We shouldn't ever get here due to the requirements on the flags, but return a helpful error just in case
-            Err(anyhow::anyhow!(
-                "Got too many options. Make sure to provide a seed and jwt or a creds path"
+        AuthConfig::Token { token } => Ok(async_nats::ConnectOptions::with_token(token)),
+        AuthConfig::UserPassword { user, password } => {
+            Ok(async_nats::ConnectOptions::with_user_and_password(
+                user, password,
             ))
         }
+        AuthConfig::TlsClientCert { cert, key } => {
+            Ok(async_nats::ConnectOptions::new().add_client_certificate(cert, key))
+        }
     }
 }
 
+/// Watches the given credential files and forces a reconnect whenever one of them changes on disk,
+/// so that a rotated creds/JWT/seed file is presented on the new connection instead of the stale
+/// credential the long-lived connection was established with.
+///
+/// Returns the watcher, which must be kept alive for the duration of the connection; dropping it
+/// stops the watch.
+pub fn watch_credentials(
+    client: Client,
+    paths: Vec<PathBuf>,
+) -> Result<notify::RecommendedWatcher> {
+    use notify::{Event, RecursiveMode, Watcher};
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+        Ok(event) if event.kind.is_modify() || event.kind.is_create() => {
+            let client = client.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.force_reconnect().await {
+                    tracing::warn!(error = %e, "failed to force reconnect after credential change");
+                }
+            });
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!(error = %e, "credential watch error"),
+    })
+    .map_err(|e| anyhow!("failed to create credential watcher: {e}"))?;
+
+    for path in paths {
+        watcher
+            .watch(&path, RecursiveMode::NonRecursive)
+            .map_err(|e| anyhow!("failed to watch credential file {path:?}: {e}"))?;
+    }
+
+    Ok(watcher)
+}
+
 /// Takes a string that could be a raw seed, or a path and does all the necessary loading and parsing steps
 async fn get_seed(seed: String) -> Result<nkeys::KeyPair> {
     // MAGIC NUMBER: Length of a seed key
@@ -103,14 +366,106 @@ async fn resolve_jwt(jwt_or_file: String) -> Result<String> {
     }
 }
 
+/// Looks up the name of an existing stream bound to the given subject, if any.
+///
+/// This issues a `$JS.API.STREAM.NAMES` request filtered by `subject`, which the server answers
+/// with the names of the streams already consuming that subject. `None` means the subject is not
+/// currently claimed by any stream.
+pub async fn stream_name_for_subject(context: &Context, subject: &str) -> Result<Option<String>> {
+    match context.stream_by_subject(subject).await {
+        Ok(name) => Ok(Some(name)),
+        // An empty stream-names response means no stream is bound to the subject: no conflict.
+        Err(e) if e.kind() == jetstream::context::GetStreamBySubjectErrorKind::NotFound => Ok(None),
+        // A real API error (permissions, non-404 JetStream errors) or a request/transport failure
+        // is not "subject is free" — surface it rather than silently proceeding to create.
+        Err(e) => Err(anyhow::anyhow!("{e:?}")),
+    }
+}
+
+/// Constructs a [`Stream`] handle from just its name, skipping the usual `STREAM.INFO` round-trip
+/// (mirroring the server's "no info" lookup). This is cheap but unverified: the handle is valid
+/// only if the stream really exists, and its first operation will report a missing stream if not.
+///
+/// Used by the ensure-helpers in `--assume-streams-exist` startup mode, where many streams are
+/// known to have been pre-provisioned and the per-stream info calls would serially add latency.
+///
+/// This bare handle performs no fallback itself — the missing-stream error surfaces on its first
+/// real operation. The `ensure_*` helpers drive that recovery: in `assume_exists` mode they take
+/// this handle, probe it once, and on a missing-stream error re-run themselves with
+/// `assume_exists = false` to create the stream.
+pub fn stream_handle_no_info(context: &Context, name: String) -> Stream {
+    context.get_stream_no_info(name)
+}
+
+/// Best-effort classification of whether a JetStream error means "the stream does not exist",
+/// used to decide when an `assume_exists` fast-path handle should fall back to full creation.
+/// Matches the server's stream-not-found API code (10059) and its human-readable phrasings.
+fn is_missing_stream_error<E: std::fmt::Debug>(err: &E) -> bool {
+    let msg = format!("{err:?}").to_lowercase();
+    msg.contains("10059") || msg.contains("not found") || msg.contains("does not exist")
+}
+
 /// A helper that ensures that the given stream name exists, using defaults to create if it does
-/// not. Returns the handle to the stream
+/// not. Returns the handle to the stream.
 pub async fn ensure_stream(
     context: &Context,
     name: String,
     subjects: Vec<String>,
     description: Option<String>,
 ) -> Result<Stream> {
+    ensure_stream_with_options(context, name, subjects, description, false, false).await
+}
+
+/// Like [`ensure_stream`] but with the overlap-reconciliation and fast-path knobs.
+///
+/// Because this creates a `WorkQueue`-retention stream, NATS will reject the creation if any of
+/// `subjects` already overlaps another stream. To surface a useful error instead of the opaque
+/// server response, a preflight lookup runs first: if a different stream already owns one of the
+/// subjects, this either returns a descriptive error naming it, or — when `adopt_existing` is set
+/// — reuses that stream's handle rather than creating a new one. When `assume_exists` is set the
+/// preflight and creation are skipped for a cheap no-info handle, self-healing if it is missing.
+pub async fn ensure_stream_with_options(
+    context: &Context,
+    name: String,
+    subjects: Vec<String>,
+    description: Option<String>,
+    adopt_existing: bool,
+    assume_exists: bool,
+) -> Result<Stream> {
+    if assume_exists {
+        // Trust the caller that the stream is pre-provisioned and skip the subject preflight.
+        let stream = stream_handle_no_info(context, name.clone());
+        match stream.get_info().await {
+            Ok(_) => return Ok(stream),
+            // The cheap handle's first operation reports the stream missing: self-heal by re-running
+            // the full path, which runs the preflight and creates the stream.
+            Err(e) if is_missing_stream_error(&e) => {
+                return Box::pin(ensure_stream_with_options(
+                    context, name, subjects, description, adopt_existing, false,
+                ))
+                .await;
+            }
+            Err(e) => return Err(anyhow::anyhow!("{e:?}")),
+        }
+    }
+    for subject in &subjects {
+        if let Some(existing) = stream_name_for_subject(context, subject).await? {
+            if existing == name {
+                continue;
+            }
+            if adopt_existing {
+                return context
+                    .get_stream(&existing)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{e:?}"));
+            }
+            return Err(anyhow::anyhow!(
+                "subject {subject:?} is already bound to stream {existing:?}, which overlaps the \
+                 work queue stream {name:?}; remove the conflicting stream or enable stream adoption"
+            ));
+        }
+    }
+
     context
         .get_or_create_stream(StreamConfig {
             name,
@@ -132,6 +487,33 @@ pub async fn ensure_status_stream(
     name: String,
     subjects: Vec<String>,
 ) -> Result<Stream> {
+    ensure_status_stream_with_options(context, name, subjects, false).await
+}
+
+/// Like [`ensure_status_stream`] but, when `assume_exists` is set, takes a no-info handle and
+/// probes it once with `get_info` instead of calling `get_or_create_stream`, self-healing to full
+/// creation if the stream turns out to be missing. The probe is itself a `STREAM.INFO` request, so
+/// this path does not avoid the info round-trip.
+pub async fn ensure_status_stream_with_options(
+    context: &Context,
+    name: String,
+    subjects: Vec<String>,
+    assume_exists: bool,
+) -> Result<Stream> {
+    if assume_exists {
+        let stream = stream_handle_no_info(context, name.clone());
+        match stream.get_info().await {
+            Ok(_) => return Ok(stream),
+            // Fast-path handle missing: self-heal by creating via the full path.
+            Err(e) if is_missing_stream_error(&e) => {
+                return Box::pin(ensure_status_stream_with_options(
+                    context, name, subjects, false,
+                ))
+                .await;
+            }
+            Err(e) => return Err(anyhow::anyhow!("{e:?}")),
+        }
+    }
     context
         .get_or_create_stream(StreamConfig {
             name,
@@ -157,6 +539,33 @@ pub async fn ensure_notify_stream(
     name: String,
     subjects: Vec<String>,
 ) -> Result<Stream> {
+    ensure_notify_stream_with_options(context, name, subjects, false).await
+}
+
+/// Like [`ensure_notify_stream`] but, when `assume_exists` is set, takes a no-info handle and
+/// probes it once with `get_info` instead of calling `get_or_create_stream`, self-healing to full
+/// creation if the stream turns out to be missing. The probe is itself a `STREAM.INFO` request, so
+/// this path does not avoid the info round-trip.
+pub async fn ensure_notify_stream_with_options(
+    context: &Context,
+    name: String,
+    subjects: Vec<String>,
+    assume_exists: bool,
+) -> Result<Stream> {
+    if assume_exists {
+        let stream = stream_handle_no_info(context, name.clone());
+        match stream.get_info().await {
+            Ok(_) => return Ok(stream),
+            // Fast-path handle missing: self-heal by creating via the full path.
+            Err(e) if is_missing_stream_error(&e) => {
+                return Box::pin(ensure_notify_stream_with_options(
+                    context, name, subjects, false,
+                ))
+                .await;
+            }
+            Err(e) => return Err(anyhow::anyhow!("{e:?}")),
+        }
+    }
     context
         .get_or_create_stream(StreamConfig {
             name,
@@ -172,6 +581,73 @@ pub async fn ensure_notify_stream(
         .map_err(|e| anyhow::anyhow!("{e:?}"))
 }
 
+/// Sends a single `sd_notify` message to the systemd notification socket named by
+/// `$NOTIFY_SOCKET`, returning `false` if the service is not running under a notify-type unit (the
+/// variable is unset) so callers can no-op outside systemd.
+///
+/// `$NOTIFY_SOCKET` may name either a filesystem path or a Linux abstract-namespace socket, which
+/// systemd encodes with a leading `@`; the kernel represents that namespace with a leading NUL
+/// byte, so the `@` is rewritten accordingly before sending.
+fn sd_notify(state: &str) -> Result<bool> {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::ffi::OsStrExt;
+
+    let value = match std::env::var_os("NOTIFY_SOCKET") {
+        Some(value) => value,
+        None => return Ok(false),
+    };
+
+    let socket = std::os::unix::net::UnixDatagram::unbound()?;
+    let bytes = value.as_bytes();
+    if let Some(abstract_name) = bytes.strip_prefix(b"@") {
+        // Abstract sockets have no filesystem entry; `from_abstract_name` supplies the leading NUL.
+        let addr = std::os::unix::net::SocketAddr::from_abstract_name(abstract_name)?;
+        socket.send_to_addr(state.as_bytes(), &addr)?;
+    } else {
+        socket.send_to(state.as_bytes(), PathBuf::from(&value))?;
+    }
+    Ok(true)
+}
+
+/// Notifies systemd that startup is complete (`READY=1`). Call this once NATS is connected and
+/// every `ensure_*` helper has returned so that units ordered after wadm only start once it is
+/// actually serving. A no-op when not running under `Type=notify`.
+pub fn notify_ready() -> Result<()> {
+    sd_notify("READY=1\n").map(|_| ())
+}
+
+/// Spawns a background task that sends `WATCHDOG=1` keepalives at half of the interval declared in
+/// `$WATCHDOG_USEC` (the systemd-recommended margin). Returns `None` when the watchdog is not
+/// enabled for this unit, so nothing is spawned outside of systemd.
+pub fn spawn_watchdog() -> Option<tokio::task::JoinHandle<()>> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    if usec == 0 {
+        return None;
+    }
+    let interval = std::time::Duration::from_micros(usec / 2);
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = sd_notify("WATCHDOG=1\n") {
+                tracing::warn!(error = %e, "failed to send systemd watchdog keepalive");
+                break;
+            }
+        }
+    }))
+}
+
+/// Emits the systemd readiness notification and, if the unit requests it, starts the watchdog
+/// keepalive task. Gated behind `enabled` so non-systemd deployments are unaffected. Call after
+/// `get_client_and_context` and all `ensure_*` helpers have succeeded.
+pub fn notify_systemd_startup(enabled: bool) -> Result<Option<tokio::task::JoinHandle<()>>> {
+    if !enabled {
+        return Ok(None);
+    }
+    notify_ready()?;
+    Ok(spawn_watchdog())
+}
+
 /// A helper that ensures that the given KV bucket exists, using defaults to create if it does
 /// not. Returns the handle to the stream
 pub async fn ensure_kv_bucket(
@@ -195,6 +671,83 @@ pub async fn ensure_kv_bucket(
     }
 }
 
+/// The stream-name prefix the server gives to streams backing KV buckets.
+const KV_STREAM_PREFIX: &str = "KV_";
+
+/// Lists the names of all KV buckets in the current context whose name starts with `prefix`.
+///
+/// KV buckets are backed by streams named `KV_<bucket>`, so this enumerates stream names via the
+/// `$JS.API.STREAM.NAMES` listing, keeps only the `KV_`-prefixed ones, and strips the prefix to
+/// recover the bucket names — then filters to wadm's naming prefix.
+pub async fn list_kv_buckets(context: &Context, prefix: &str) -> Result<Vec<String>> {
+    use futures::TryStreamExt;
+
+    let names: Vec<String> = context
+        .stream_names()
+        .map_err(|e| anyhow!("{e:?}"))
+        .try_collect()
+        .await?;
+    Ok(names
+        .into_iter()
+        .filter_map(|name| name.strip_prefix(KV_STREAM_PREFIX).map(str::to_string))
+        .filter(|bucket| bucket.starts_with(prefix))
+        .collect())
+}
+
+/// Lists the names of all wadm-owned streams in the current context whose name starts with
+/// `prefix`. KV-backing streams are excluded; use [`list_kv_buckets`] for those.
+pub async fn list_wadm_streams(context: &Context, prefix: &str) -> Result<Vec<String>> {
+    use futures::TryStreamExt;
+
+    let names: Vec<String> = context
+        .stream_names()
+        .map_err(|e| anyhow!("{e:?}"))
+        .try_collect()
+        .await?;
+    Ok(names
+        .into_iter()
+        .filter(|name| name.starts_with(prefix) && !name.starts_with(KV_STREAM_PREFIX))
+        .collect())
+}
+
+/// Deletes every KV bucket and wadm stream matching `prefix` that does not correspond to one of the
+/// `active_lattices`, reclaiming storage left behind by torn-down environments. Returns the names
+/// of everything that was pruned.
+///
+/// A bucket or stream is considered active when its name ends with the lattice id of a still-active
+/// lattice.
+pub async fn prune_stale(
+    context: &Context,
+    prefix: &str,
+    active_lattices: &[String],
+) -> Result<Vec<String>> {
+    let is_active = |name: &str| active_lattices.iter().any(|lattice| name.ends_with(lattice));
+
+    let mut pruned = Vec::new();
+
+    for bucket in list_kv_buckets(context, prefix).await? {
+        if !is_active(&bucket) {
+            context
+                .delete_key_value(&bucket)
+                .await
+                .map_err(|e| anyhow!("failed to delete KV bucket {bucket:?}: {e:?}"))?;
+            pruned.push(format!("{KV_STREAM_PREFIX}{bucket}"));
+        }
+    }
+
+    for stream in list_wadm_streams(context, prefix).await? {
+        if !is_active(&stream) {
+            context
+                .delete_stream(&stream)
+                .await
+                .map_err(|e| anyhow!("failed to delete stream {stream:?}: {e:?}"))?;
+            pruned.push(stream);
+        }
+    }
+
+    Ok(pruned)
+}
+
 #[cfg(test)]
 mod test {
     use super::resolve_jwt;